@@ -1,7 +1,7 @@
 use std::{ops::Add, path::PathBuf, str::FromStr, time::Duration};
 
-use chrono::NaiveDateTime;
-use tasktree_core::Config;
+use chrono::{NaiveDate, NaiveDateTime};
+use tasktree_core::{Config, Priority};
 
 lazy_static::lazy_static! {
 	pub static ref DEFAULT_TASKSETS_PATH: PathBuf = if cfg!(windows) {
@@ -34,6 +34,36 @@ fn parse_and_unwrap_fuzzydate(s: &str) -> NaiveDateTime {
 	fuzzydate::parse(s).unwrap_or_else(|_| panic!("Could not parse date: {}", s))
 }
 
+fn parse_and_unwrap_priority(s: &str) -> Priority {
+	s.parse()
+		.unwrap_or_else(|_| panic!("Could not parse priority: {}", s))
+}
+
+fn parse_and_unwrap_date(s: &str) -> NaiveDate {
+	fuzzydate::parse(s)
+		.unwrap_or_else(|_| panic!("Could not parse date: {}", s))
+		.date()
+}
+
+/// The output format for a read command.
+#[derive(Debug, Clone, Copy)]
+pub enum OutputFormat {
+	/// Each command's default output, unchanged from before `--format` existed: for
+	/// `list-tasks`, a JSON `{name: task}` object, so scripts parsing it keep working; for
+	/// `show-tree`, the dependency graph printed as a text tree (not JSON).
+	Json,
+	/// A colorized table, for humans reading a terminal.
+	Table,
+}
+
+fn parse_and_unwrap_format(s: &str) -> OutputFormat {
+	match s {
+		"json" => OutputFormat::Json,
+		"table" => OutputFormat::Table,
+		other => panic!("Unknown output format: {} (expected json or table)", other),
+	}
+}
+
 #[derive(StructOpt, Debug)]
 pub struct GlobalArgs {
 	#[structopt(long = "tasksets-path", short = "T", env = "TASKTREE_TASKSETS_PATH", default_value=DEFAULT_TASKSETS_PATH.to_str().unwrap(), help = "Path to the directory containing the task sets.\n")]
@@ -107,9 +137,33 @@ pub enum TaskTree {
 		name = "list-tasks",
 		about = "Lists all tasks in the selected task set."
 	)]
-	ListTasks,
+	ListTasks {
+		#[structopt(
+			long = "query",
+			short = "q",
+			help = "A filter query selecting which tasks to list, e.g. \"complete == false and is-leaf\".\n"
+		)]
+		query: Option<String>,
+		#[structopt(
+			long = "format",
+			short = "F",
+			default_value = "json",
+			help = "The output format: json or table.\n",
+			parse(from_str = parse_and_unwrap_format)
+		)]
+		format: OutputFormat,
+	},
 	#[structopt(name = "show-tree", about = "Prints the tree of tasks.")]
-	ShowTree,
+	ShowTree {
+		#[structopt(
+			long = "format",
+			short = "F",
+			default_value = "json",
+			help = "The output format: json (the dependency graph as a text tree) or table.\n",
+			parse(from_str = parse_and_unwrap_format)
+		)]
+		format: OutputFormat,
+	},
 	#[structopt(name = "add-task", about = "Add a task.")]
 	AddTask {
 		#[structopt(help = "The name of the task.\n")]
@@ -139,6 +193,13 @@ pub enum TaskTree {
 		complete: bool,
 		#[structopt(long = "due", short = "d", help = "The due date of the task.\n", parse(from_str = parse_and_unwrap_fuzzydate))]
 		due: Option<NaiveDateTime>,
+		#[structopt(
+			long = "recurrence",
+			short = "R",
+			help = "How often the task recurs; on completion, it is regenerated with its due date advanced by this interval.\n",
+			parse(from_str = parse_and_unwrap_duration)
+		)]
+		recurrence: Option<Duration>,
 	},
 	#[structopt(name = "remove-task", about = "Remove a task.")]
 	RemoveTask {
@@ -153,6 +214,12 @@ pub enum TaskTree {
 			help = "Whether the task is complete\n"
 		)]
 		complete: Option<bool>,
+		#[structopt(
+			long = "force",
+			short = "F",
+			help = "Complete the task even if it has incomplete dependencies.\n"
+		)]
+		force: bool,
 		#[structopt(help = "The name of the task.\n")]
 		name: Vec<String>,
 	},
@@ -161,6 +228,45 @@ pub enum TaskTree {
 		#[structopt(help = "The name of the task.\n")]
 		name: Vec<String>,
 	},
+	#[structopt(name = "set-priority", about = "Set the priority of a task.")]
+	SetPriority {
+		#[structopt(
+			long = "priority",
+			short = "p",
+			help = "The priority to set.\n",
+			parse(from_str = parse_and_unwrap_priority)
+		)]
+		priority: Priority,
+		#[structopt(help = "The name of the task.\n")]
+		name: Vec<String>,
+	},
+	#[structopt(
+		name = "log-time",
+		about = "Log time spent working on a task."
+	)]
+	LogTime {
+		#[structopt(help = "The name of the task.\n")]
+		name: String,
+		#[structopt(
+			long = "duration",
+			short = "t",
+			help = "How long the work session lasted.\n",
+			parse(from_str = parse_and_unwrap_duration)
+		)]
+		duration: Duration,
+		#[structopt(
+			long = "date",
+			short = "d",
+			help = "The date the work was logged on. Defaults to today.\n",
+			parse(from_str = parse_and_unwrap_date)
+		)]
+		date: Option<NaiveDate>,
+	},
 	#[structopt(name = "lint", about = "Lint the task tree.")]
 	Lint,
+	#[structopt(
+		name = "next",
+		about = "Prints the incomplete tasks in the order they become actionable."
+	)]
+	Next,
 }
@@ -2,6 +2,7 @@ use structopt::StructOpt;
 use tasktree_core::Config;
 pub mod args;
 pub mod loader;
+pub mod query;
 pub mod task_edit;
 
 #[macro_use]
@@ -12,15 +13,15 @@ fn main() {
 	let _config = Config::default() + &all_opt;
 	match all_opt.cmd {
 		args::TaskTree::License => println!(include_str!("../../LICENSE")),
-		args::TaskTree::ListTasks => {
-			let tasks = loader::load_tasksets(&all_opt);
-			println!("{}", serde_json::to_string_pretty(&tasks.tasks).unwrap());
-		}
+		args::TaskTree::ListTasks { .. } => task_edit::list_tasks(all_opt),
 		args::TaskTree::AddTask { .. } => task_edit::add_task(all_opt),
 		args::TaskTree::RemoveTask { .. } => task_edit::remove_task(all_opt),
-		args::TaskTree::ShowTree => task_edit::show_tree(all_opt),
+		args::TaskTree::ShowTree { .. } => task_edit::show_tree(all_opt),
 		args::TaskTree::CompleteTask { .. } => task_edit::complete_task(all_opt),
 		args::TaskTree::ShowTask { .. } => task_edit::show_task(all_opt),
+		args::TaskTree::SetPriority { .. } => task_edit::set_priority(all_opt),
+		args::TaskTree::LogTime { .. } => task_edit::log_time(all_opt),
 		args::TaskTree::Lint => task_edit::lint(all_opt),
+		args::TaskTree::Next => task_edit::next(all_opt),
 	}
 }
@@ -0,0 +1,198 @@
+//! A small filter/query language for `list-tasks --query`.
+//!
+//! A query is a sequence of predicates over a task's fields, combined with `and`/`or`,
+//! e.g. `complete == false and due < tomorrow`.
+
+use chrono::{DateTime, Local, TimeZone};
+use tasktree_core::{Task, Tree};
+
+/// A parsed `--query` expression.
+#[derive(Debug, Clone)]
+pub enum Query {
+	CompleteEq(bool),
+	DueLt(DateTime<Local>),
+	DueGt(DateTime<Local>),
+	Symbolic,
+	HasIncompleteDeps,
+	IsLeaf,
+	IsDependent,
+	And(Box<Query>, Box<Query>),
+	Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+	/// Parse a query string into a `Query` AST.
+	pub fn parse(input: &str) -> Result<Self, String> {
+		let tokens: Vec<&str> = input.split_whitespace().collect();
+		let (query, rest) = parse_or(&tokens)?;
+		if !rest.is_empty() {
+			return Err(format!("unexpected trailing tokens: {:?}", rest));
+		}
+		Ok(query)
+	}
+
+	/// Evaluate the query against a single task, given the tree it belongs to.
+	pub fn eval(&self, name: &str, task: &Task, tree: &Tree) -> bool {
+		match self {
+			Query::CompleteEq(expected) => task.complete == *expected,
+			Query::DueLt(date) => task.due.is_some_and(|due| due < *date),
+			Query::DueGt(date) => task.due.is_some_and(|due| due > *date),
+			Query::Symbolic => task.symbolic,
+			Query::HasIncompleteDeps => task
+				.depends_on
+				.iter()
+				.any(|dep| !tree.tasks.get(dep).is_some_and(|dep| dep.complete)),
+			Query::IsLeaf => task.depends_on.is_empty(),
+			Query::IsDependent => tree
+				.tasks
+				.values()
+				.any(|other| other.depends_on.iter().any(|dep| dep == name)),
+			Query::And(lhs, rhs) => lhs.eval(name, task, tree) && rhs.eval(name, task, tree),
+			Query::Or(lhs, rhs) => lhs.eval(name, task, tree) || rhs.eval(name, task, tree),
+		}
+	}
+}
+
+fn parse_or<'a>(tokens: &'a [&'a str]) -> Result<(Query, &'a [&'a str]), String> {
+	let (mut lhs, mut rest) = parse_and(tokens)?;
+	while rest.first() == Some(&"or") {
+		let (rhs, next) = parse_and(&rest[1..])?;
+		lhs = Query::Or(Box::new(lhs), Box::new(rhs));
+		rest = next;
+	}
+	Ok((lhs, rest))
+}
+
+fn parse_and<'a>(tokens: &'a [&'a str]) -> Result<(Query, &'a [&'a str]), String> {
+	let (mut lhs, mut rest) = parse_atom(tokens)?;
+	while rest.first() == Some(&"and") {
+		let (rhs, next) = parse_atom(&rest[1..])?;
+		lhs = Query::And(Box::new(lhs), Box::new(rhs));
+		rest = next;
+	}
+	Ok((lhs, rest))
+}
+
+fn parse_atom<'a>(tokens: &'a [&'a str]) -> Result<(Query, &'a [&'a str]), String> {
+	match tokens {
+		["complete", "==", value, rest @ ..] => Ok((Query::CompleteEq(parse_bool(value)?), rest)),
+		["due", "<", value, rest @ ..] => Ok((Query::DueLt(parse_due(value)?), rest)),
+		["due", ">", value, rest @ ..] => Ok((Query::DueGt(parse_due(value)?), rest)),
+		["symbolic", rest @ ..] => Ok((Query::Symbolic, rest)),
+		["has-incomplete-deps", rest @ ..] => Ok((Query::HasIncompleteDeps, rest)),
+		["is-leaf", rest @ ..] => Ok((Query::IsLeaf, rest)),
+		["is-dependent", rest @ ..] => Ok((Query::IsDependent, rest)),
+		[other, ..] => Err(format!("unexpected token: {}", other)),
+		[] => Err("unexpected end of query".to_string()),
+	}
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+	match value {
+		"true" => Ok(true),
+		"false" => Ok(false),
+		other => Err(format!("expected true or false, got {}", other)),
+	}
+}
+
+fn parse_due(value: &str) -> Result<DateTime<Local>, String> {
+	let naive =
+		fuzzydate::parse(value).map_err(|_| format!("could not parse date: {}", value))?;
+	Ok(Local.from_local_datetime(&naive).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+	use chrono::{Local, TimeZone};
+	use tasktree_core::{Task, Tree};
+
+	use super::Query;
+
+	fn task() -> Task {
+		Task::default()
+	}
+
+	fn tree_of(tasks: Vec<(&str, Task)>) -> Tree {
+		let mut tree = Tree::default();
+		for (name, task) in tasks {
+			tree.tasks.insert(name.to_string(), task);
+		}
+		tree
+	}
+
+	#[test]
+	fn parses_and_evaluates_complete_predicate() {
+		let mut done = task();
+		done.complete = true;
+		let tree = tree_of(vec![("a", done.clone())]);
+		assert!(Query::parse("complete == true")
+			.unwrap()
+			.eval("a", &done, &tree));
+		assert!(!Query::parse("complete == false")
+			.unwrap()
+			.eval("a", &done, &tree));
+	}
+
+	#[test]
+	fn parses_and_evaluates_due_predicates() {
+		let mut t = task();
+		t.due = Some(Local.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap());
+		let tree = tree_of(vec![("a", t.clone())]);
+		assert!(Query::parse("due < 2025-01-01")
+			.unwrap()
+			.eval("a", &t, &tree));
+		assert!(!Query::parse("due > 2025-01-01")
+			.unwrap()
+			.eval("a", &t, &tree));
+	}
+
+	#[test]
+	fn parses_and_evaluates_symbolic_predicate() {
+		let mut sym = task();
+		sym.symbolic = true;
+		let tree = tree_of(vec![("a", sym.clone())]);
+		assert!(Query::parse("symbolic").unwrap().eval("a", &sym, &tree));
+		assert!(!Query::parse("symbolic").unwrap().eval("b", &task(), &tree));
+	}
+
+	#[test]
+	fn parses_and_evaluates_dependency_predicates() {
+		let mut dependent = task();
+		dependent.depends_on = vec!["leaf".to_string()];
+		let tree = tree_of(vec![("leaf", task()), ("dependent", dependent.clone())]);
+
+		assert!(Query::parse("is-leaf")
+			.unwrap()
+			.eval("leaf", &task(), &tree));
+		assert!(!Query::parse("is-leaf")
+			.unwrap()
+			.eval("dependent", &dependent, &tree));
+		assert!(Query::parse("is-dependent")
+			.unwrap()
+			.eval("leaf", &task(), &tree));
+		assert!(Query::parse("has-incomplete-deps")
+			.unwrap()
+			.eval("dependent", &dependent, &tree));
+	}
+
+	#[test]
+	fn parses_and_or_combinations() {
+		let t = task();
+		let tree = tree_of(vec![("a", t.clone())]);
+		assert!(Query::parse("complete == false and is-leaf")
+			.unwrap()
+			.eval("a", &t, &tree));
+		assert!(Query::parse("complete == true or is-leaf")
+			.unwrap()
+			.eval("a", &t, &tree));
+		assert!(!Query::parse("complete == true and is-leaf")
+			.unwrap()
+			.eval("a", &t, &tree));
+	}
+
+	#[test]
+	fn parse_rejects_unknown_predicates_and_trailing_tokens() {
+		assert!(Query::parse("not-a-real-predicate").is_err());
+		assert!(Query::parse("is-leaf extra").is_err());
+	}
+}
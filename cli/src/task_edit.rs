@@ -1,11 +1,78 @@
+use std::collections::HashMap;
 use std::fs;
+use std::time::Duration;
 
 use chrono::Local;
 use chrono::TimeZone;
+use colored::Colorize;
+use prettytable::{row, Table};
 use ptree::graph::print_graph;
-use tasktree_core::{Task, Tree};
+use tasktree_core::{Task, TimeEntry, Tree};
 
+use crate::args::OutputFormat;
 use crate::loader;
+use crate::query::Query;
+
+/// Renders tasks as a colorized table: overdue tasks are flagged red, completed tasks green.
+fn print_table<'a>(tasks: impl IntoIterator<Item = (&'a String, &'a Task)>) {
+	let mut table = Table::new();
+	table.add_row(row!["Name", "Complete", "Due", "Estimated", "Deps"]);
+	for (name, task) in tasks {
+		let due = task
+			.due
+			.map(|due| due.format("%Y-%m-%d").to_string())
+			.unwrap_or_else(|| "-".to_string());
+		let estimated = task
+			.estimated_time
+			.map(|duration| format!("{:?}", duration))
+			.unwrap_or_else(|| "-".to_string());
+		let (name, due) = if task.complete {
+			(name.green(), due.green())
+		} else if task.is_overdue() {
+			(name.red(), due.red())
+		} else {
+			(name.normal(), due.normal())
+		};
+		table.add_row(row![
+			name,
+			task.complete,
+			due,
+			estimated,
+			task.depends_on.len()
+		]);
+	}
+	table.printstd();
+}
+
+pub fn list_tasks(args: crate::args::GlobalArgs) {
+	if let crate::args::TaskTree::ListTasks { query, format } = &args.cmd {
+		let format = *format;
+		let task_tree = loader::load_tasksets(&args);
+		let query = query
+			.as_deref()
+			.map(|query| Query::parse(query).unwrap_or_else(|e| panic!("invalid query: {}", e)));
+		let matching: Vec<_> = task_tree
+			.sorted_tasks()
+			.into_iter()
+			.filter(|(name, task)| {
+				query
+					.as_ref()
+					.is_none_or(|query| query.eval(name, task, &task_tree))
+			})
+			.collect();
+		match format {
+			OutputFormat::Table => print_table(matching),
+			OutputFormat::Json => {
+				// Preserve the `{name: task}` object shape scripts already depend on,
+				// rather than the `[name, task]` pair array `sorted_tasks` returns.
+				let matching: HashMap<&String, &Task> = matching.into_iter().collect();
+				println!("{}", serde_json::to_string_pretty(&matching).unwrap())
+			}
+		}
+	} else {
+		unreachable!()
+	}
+}
 
 pub fn add_task(args: crate::args::GlobalArgs) {
 	if let crate::args::TaskTree::AddTask {
@@ -16,6 +83,7 @@ pub fn add_task(args: crate::args::GlobalArgs) {
 		complete,
 		due,
 		symbolic,
+		recurrence,
 	} = args.cmd
 	{
 		if let [taskset_name] = &args.taskset[..] {
@@ -34,6 +102,7 @@ pub fn add_task(args: crate::args::GlobalArgs) {
 			task.complete = complete;
 			task.due = due.map(|due| Local.from_local_datetime(&due).unwrap());
 			task.symbolic = symbolic;
+			task.recurrence = recurrence;
 			taskset.tasks.insert(name.to_owned(), task);
 			taskset.populate_tree().unwrap();
 			fs::write(path, toml::to_string(&taskset).unwrap()).expect("Couldn't write taskset");
@@ -56,11 +125,24 @@ pub fn remove_task(args: crate::args::GlobalArgs) {
 				&fs::read_to_string(path.clone()).unwrap_or_else(|_| "tasks = {}".to_string()),
 			)
 			.expect("refusing to overwrite invalid taskset, please check manually");
-			for task in &name {
-				taskset.tasks.remove(task);
+			let archive_path = args
+				.tasksets_path
+				.clone()
+				.join(taskset_name.to_owned() + ".archived.toml");
+			let mut archive: Tree = toml::from_str(
+				&fs::read_to_string(archive_path.clone())
+					.unwrap_or_else(|_| "tasks = {}".to_string()),
+			)
+			.expect("refusing to overwrite invalid archive, please check manually");
+			for task_name in &name {
+				if let Some(task) = taskset.tasks.remove(task_name) {
+					archive.tasks.insert(task_name.to_owned(), task);
+				}
 			}
 			taskset.populate_tree().unwrap();
 			fs::write(path, toml::to_string(&taskset).unwrap()).expect("Couldn't write taskset");
+			fs::write(archive_path, toml::to_string(&archive).unwrap())
+				.expect("Couldn't write archive");
 		}
 	} else {
 		unreachable!()
@@ -68,7 +150,84 @@ pub fn remove_task(args: crate::args::GlobalArgs) {
 }
 
 pub fn complete_task(args: crate::args::GlobalArgs) {
-	if let crate::args::TaskTree::CompleteTask { name, complete } = args.cmd {
+	if let crate::args::TaskTree::CompleteTask {
+		name,
+		complete,
+		force,
+	} = args.cmd
+	{
+		for taskset_name in &args.taskset {
+			let path = args
+				.tasksets_path
+				.clone()
+				.join(taskset_name.to_owned() + ".toml");
+			let mut taskset: Tree = toml::from_str(
+				&fs::read_to_string(path.clone()).unwrap_or_else(|_| "tasks = {}".to_string()),
+			)
+			.expect("refusing to overwrite invalid taskset, please check manually");
+			let completing = complete.unwrap_or(true);
+			for task_name in &name {
+				if completing {
+					let has_incomplete_deps = taskset
+						.tasks
+						.get(task_name)
+						.map(|task| {
+							task.depends_on.iter().any(|dep| {
+								!taskset.tasks.get(dep).is_none_or(|dep| dep.complete)
+							})
+						})
+						.unwrap_or(false);
+					if has_incomplete_deps && !force {
+						eprintln!(
+							"refusing to complete {}: it has incomplete dependencies (use --force to override)",
+							task_name
+						);
+						continue;
+					} else if has_incomplete_deps {
+						eprintln!(
+							"warning: completing {} with incomplete dependencies",
+							task_name
+						);
+					}
+				}
+				if let Some(task) = taskset.tasks.get_mut(task_name) {
+					task.complete = completing;
+				}
+				if completing {
+					if let Some(interval) = taskset.tasks.get(task_name).and_then(|t| t.recurrence)
+					{
+						let mut next = taskset.tasks[task_name].clone();
+						next.complete = false;
+						// Each occurrence starts with no logged time of its own.
+						next.time_entries = Vec::new();
+						let base = next.due.unwrap_or_else(Local::now);
+						next.due = Some(base + chrono::Duration::from_std(interval).unwrap());
+						let mut next_name =
+							format!("{} ({})", task_name, next.due.unwrap().format("%Y-%m-%d"));
+						let mut suffix = 2;
+						while taskset.tasks.contains_key(&next_name) {
+							next_name = format!(
+								"{} ({}) #{}",
+								task_name,
+								next.due.unwrap().format("%Y-%m-%d"),
+								suffix
+							);
+							suffix += 1;
+						}
+						taskset.tasks.insert(next_name, next);
+					}
+				}
+			}
+			taskset.populate_tree().unwrap();
+			fs::write(path, toml::to_string(&taskset).unwrap()).expect("Couldn't write taskset");
+		}
+	} else {
+		unreachable!()
+	}
+}
+
+pub fn set_priority(args: crate::args::GlobalArgs) {
+	if let crate::args::TaskTree::SetPriority { name, priority } = args.cmd {
 		for taskset_name in &args.taskset {
 			let path = args
 				.tasksets_path
@@ -80,7 +239,7 @@ pub fn complete_task(args: crate::args::GlobalArgs) {
 			.expect("refusing to overwrite invalid taskset, please check manually");
 			for task in &name {
 				if let Some(task) = taskset.tasks.get_mut(task) {
-					task.complete = complete.unwrap_or(true);
+					task.priority = priority;
 				}
 			}
 			taskset.populate_tree().unwrap();
@@ -91,6 +250,38 @@ pub fn complete_task(args: crate::args::GlobalArgs) {
 	}
 }
 
+pub fn log_time(args: crate::args::GlobalArgs) {
+	if let crate::args::TaskTree::LogTime {
+		name,
+		duration,
+		date,
+	} = args.cmd
+	{
+		if let [taskset_name] = &args.taskset[..] {
+			let path = args
+				.tasksets_path
+				.clone()
+				.join(taskset_name.to_owned() + ".toml");
+			let mut taskset: Tree = toml::from_str(
+				&fs::read_to_string(path.clone()).unwrap_or_else(|_| "tasks = {}".to_string()),
+			)
+			.expect("refusing to overwrite invalid taskset, please check manually");
+			if let Some(task) = taskset.tasks.get_mut(&name) {
+				task.time_entries.push(TimeEntry {
+					logged_date: date.unwrap_or_else(|| Local::now().date_naive()),
+					duration,
+				});
+			}
+			taskset.populate_tree().unwrap();
+			fs::write(path, toml::to_string(&taskset).unwrap()).expect("Couldn't write taskset");
+		} else {
+			panic!("exactly one taskset must be specified for log_time.")
+		}
+	} else {
+		unreachable!()
+	}
+}
+
 pub fn show_task(args: crate::args::GlobalArgs) {
 	if let crate::args::TaskTree::ShowTask { name } = args.cmd {
 		for taskset_name in &args.taskset {
@@ -108,6 +299,13 @@ pub fn show_task(args: crate::args::GlobalArgs) {
 						"{}",
 						serde_json::to_string_pretty(&task).unwrap_or_else(|_| "".to_string())
 					);
+					let logged: Duration =
+						task.time_entries.iter().map(|entry| entry.duration).sum();
+					if let Some(estimated) = task.estimated_time {
+						println!("logged {:?} of {:?} estimated", logged, estimated);
+					} else if !task.time_entries.is_empty() {
+						println!("logged {:?}", logged);
+					}
 				}
 			}
 		}
@@ -117,14 +315,19 @@ pub fn show_task(args: crate::args::GlobalArgs) {
 }
 
 pub fn show_tree(args: crate::args::GlobalArgs) {
-	if let crate::args::TaskTree::ShowTree = args.cmd {
+	if let crate::args::TaskTree::ShowTree { format } = args.cmd {
 		let task_tree = loader::load_tasksets(&args);
-		let indices = task_tree.tree.node_indices().collect::<Vec<_>>();
-		let root = indices
-			.iter()
-			.find(|&&i| task_tree.tree.node_weight(i).unwrap().is_root)
-			.unwrap();
-		print_graph(&task_tree.tree, *root).unwrap();
+		match format {
+			OutputFormat::Table => print_table(task_tree.sorted_tasks()),
+			OutputFormat::Json => {
+				let indices = task_tree.tree.node_indices().collect::<Vec<_>>();
+				let root = indices
+					.iter()
+					.find(|&&i| task_tree.tree.node_weight(i).unwrap().is_root)
+					.unwrap();
+				print_graph(&task_tree.tree, *root).unwrap();
+			}
+		}
 	} else {
 		unreachable!()
 	}
@@ -141,3 +344,19 @@ pub fn lint(args: crate::args::GlobalArgs) {
 		println!("no errors found.");
 	}
 }
+
+pub fn next(args: crate::args::GlobalArgs) {
+	let task_tree = loader::load_tasksets(&args);
+	match task_tree.ready_order() {
+		Ok(order) => {
+			for name in order {
+				println!("{}", name);
+			}
+		}
+		Err(errors) => {
+			for error in errors {
+				println!("{:#?}", error);
+			}
+		}
+	}
+}
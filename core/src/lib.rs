@@ -7,7 +7,7 @@ use std::{
 	time::Duration,
 };
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
 use petgraph::{graph::NodeIndex, Graph};
 use thiserror::Error;
 
@@ -71,6 +71,13 @@ pub struct Tree {
 }
 
 impl Tree {
+	/// Returns the tasks sorted by due date (tasks with no due date last), breaking ties
+	/// so that among otherwise-equivalent tasks the higher priority surfaces first.
+	pub fn sorted_tasks(&self) -> Vec<(&String, &Task)> {
+		let mut tasks: Vec<_> = self.tasks.iter().collect();
+		tasks.sort_by(|(name_a, a), (name_b, b)| task_order(name_a, a, name_b, b));
+		tasks
+	}
 	/// Populate the generated dependency tree.
 	pub fn populate_tree(&mut self) -> Result<(), anyhow::Error> {
 		self.tree = Graph::new();
@@ -100,18 +107,25 @@ impl Tree {
 			complete: true,
 			is_root: true,
 		});
-		// Create all nodes
-		let indices: HashMap<String, NodeIndex> = self
-			.tasks
-			.iter()
-			.map(|(name, task)| {
+		// Create all nodes, in due-date/priority order so that higher-priority tasks
+		// surface first among otherwise-equivalent tasks. Collect the order into owned
+		// data first, since `sorted_tasks` borrows `self` and can't stay live across the
+		// node-creation loop below, which also mutably borrows `self.tree`.
+		let order: Vec<(String, bool)> = self
+			.sorted_tasks()
+			.into_iter()
+			.map(|(name, task)| (name.clone(), task.complete))
+			.collect();
+		let indices: HashMap<String, NodeIndex> = order
+			.into_iter()
+			.map(|(name, complete)| {
 				let index = self.tree.add_node(TaskNode {
 					name: name.clone(),
-					complete: task.complete,
+					complete,
 					is_root: false,
 				});
 				self.tree.add_edge(root, index, false);
-				(name.clone(), index)
+				(name, index)
 			})
 			.collect();
 		// Add edges
@@ -164,6 +178,20 @@ impl Tree {
 				}
 			}
 		}
+		// Detect symbolic tasks with a recurrence: a symbolic task is completed by its
+		// dependencies, so it has no completion event to advance the recurrence from.
+		{
+			for (name, task) in &self.tasks {
+				if task.symbolic && task.recurrence.is_some() {
+					errors.push(
+						TaskTreeCoreError::SymbolicRecurring {
+							task_name: name.clone(),
+						}
+						.into(),
+					);
+				}
+			}
+		}
 		// Detect cyclic
 		let cyclic = {
 			fn visitor(
@@ -173,7 +201,12 @@ impl Tree {
 			) -> HashSet<(String, String)> {
 				let mut visited = visited.clone();
 				visited.push(task.clone());
-				for i in &tasks[&task].depends_on {
+				// A dangling dependency is reported separately below; don't panic on it here.
+				let current = match tasks.get(&task) {
+					Some(current) => current,
+					None => return HashSet::new(),
+				};
+				for i in &current.depends_on {
 					if visited.contains(&i) {
 						return {
 							let mut set = HashSet::new();
@@ -182,7 +215,7 @@ impl Tree {
 						};
 					}
 				}
-				tasks[&task]
+				current
 					.depends_on
 					.iter()
 					.map(|n| visitor(tasks, n.clone(), &visited))
@@ -215,7 +248,7 @@ impl Tree {
 					continue;
 				}
 				let due_date = task.due.unwrap();
-				if due_date < now && !task.complete {
+				if task.is_overdue() {
 					errors.push(
 						TaskTreeCoreError::ImpossibleTaskError {
 							task_name: name.clone(),
@@ -230,10 +263,14 @@ impl Tree {
 					task: String,
 					completion_time: &mut std::time::Duration,
 				) {
-					if !tasks[&task].complete {
-						*completion_time =
-							*completion_time + tasks[&task].estimated_time.unwrap_or_default();
-						for dep in tasks[&task].depends_on.iter() {
+					// A dangling dependency is reported separately; don't panic on it here.
+					let current = match tasks.get(&task) {
+						Some(current) => current,
+						None => return,
+					};
+					if !current.complete {
+						*completion_time = *completion_time + current.estimated_time.unwrap_or_default();
+						for dep in current.depends_on.iter() {
 							visitor(tasks, dep.clone(), completion_time);
 						}
 					}
@@ -268,12 +305,113 @@ impl Tree {
 				}
 			}
 		}
+		// Detect completed tasks with an incomplete dependency
+		{
+			for (name, task) in &self.tasks {
+				if !task.complete {
+					continue;
+				}
+				for dependency in &task.depends_on {
+					if let Some(dep_task) = self.tasks.get(dependency) {
+						if !dep_task.complete {
+							errors.push(
+								TaskTreeCoreError::IncompleteDependency {
+									task_name: name.clone(),
+									dependency: dependency.clone(),
+								}
+								.into(),
+							);
+						}
+					}
+				}
+			}
+		}
+		// Detect tasks whose logged time already exceeds their estimate
+		{
+			for (name, task) in &self.tasks {
+				if let Some(estimated) = task.estimated_time {
+					let logged: Duration =
+						task.time_entries.iter().map(|entry| entry.duration).sum();
+					if logged > estimated {
+						errors.push(
+							TaskTreeCoreError::LoggedTimeExceedsEstimate {
+								task_name: name.clone(),
+							}
+							.into(),
+						);
+					}
+				}
+			}
+		}
 		if !errors.is_empty() {
 			Err(errors)
 		} else {
 			Ok(())
 		}
 	}
+	/// Returns the incomplete, non-symbolic tasks in a runnable order: tasks whose
+	/// dependencies are all complete are emitted first, in due-date/priority order, then the
+	/// process repeats against the remaining tasks (Kahn-style layering) until everything
+	/// reachable is placed. Symbolic tasks are excluded, since they are completed by their
+	/// dependencies rather than worked on directly.
+	///
+	/// Refuses to run if [`Tree::lint_tree`] reports a [`TaskTreeCoreError::CyclicDependency`]
+	/// or a [`TaskTreeCoreError::NonexistentDependency`], since no valid order exists in
+	/// either case: a dependency that can never complete leaves whatever depends on it
+	/// permanently unready.
+	pub fn ready_order(&self) -> Result<Vec<String>, Vec<anyhow::Error>> {
+		if let Err(errors) = self.lint_tree() {
+			let blocking: Vec<anyhow::Error> = errors
+				.into_iter()
+				.filter(|error| {
+					matches!(
+						error.downcast_ref::<TaskTreeCoreError>(),
+						Some(
+							TaskTreeCoreError::CyclicDependency { .. }
+								| TaskTreeCoreError::NonexistentDependency { .. }
+						)
+					)
+				})
+				.collect();
+			if !blocking.is_empty() {
+				return Err(blocking);
+			}
+		}
+		let mut done: HashSet<String> = self
+			.tasks
+			.iter()
+			.filter(|(_, task)| task.complete)
+			.map(|(name, _)| name.clone())
+			.collect();
+		let mut order = vec![];
+		loop {
+			let newly_ready: Vec<(&String, &Task)> = self
+				.tasks
+				.iter()
+				.filter(|(name, task)| {
+					!task.complete
+						&& !done.contains(*name)
+						&& task.depends_on.iter().all(|dep| done.contains(dep))
+				})
+				.collect();
+			if newly_ready.is_empty() {
+				break;
+			}
+			// Symbolic tasks complete automatically once their dependencies do, so fold them
+			// into `done` to unblock their dependents without listing them as actionable.
+			let (symbolic, mut actionable): (Vec<_>, Vec<_>) =
+				newly_ready.into_iter().partition(|(_, task)| task.symbolic);
+			for (name, _) in symbolic {
+				done.insert(name.clone());
+			}
+			actionable.sort_by(|(name_a, a), (name_b, b)| task_order(name_a, a, name_b, b));
+			for (name, _) in actionable {
+				done.insert(name.clone());
+				order.push(name.clone());
+			}
+		}
+		Ok(order)
+	}
 }
 
 impl Add<&Tree> for Tree {
@@ -315,6 +453,15 @@ pub enum TaskTreeCoreError {
 		task_name: String,
 		dependency: String,
 	},
+	#[error("logged time already exceeds the estimated time")]
+	LoggedTimeExceedsEstimate { task_name: String },
+	#[error("a recurring task cannot be symbolic")]
+	SymbolicRecurring { task_name: String },
+	#[error("task is complete but has an incomplete dependency")]
+	IncompleteDependency {
+		task_name: String,
+		dependency: String,
+	},
 }
 
 /// The reason why a task is impossible.
@@ -340,4 +487,70 @@ pub struct Task {
 	pub complete: bool,
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub due: Option<DateTime<Local>>,
+	#[serde(default)]
+	pub priority: Priority,
+	#[serde(default)]
+	pub time_entries: Vec<TimeEntry>,
+	/// How often the task recurs: when it is marked complete, it is cloned with `due`
+	/// advanced by this interval and `complete` reset to `false`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub recurrence: Option<Duration>,
+}
+
+impl Task {
+	/// Whether the task is past its due date without being complete.
+	pub fn is_overdue(&self) -> bool {
+		self.due.is_some_and(|due| due < Local::now()) && !self.complete
+	}
+}
+
+/// A single logged work session against a task.
+#[repr(C)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct TimeEntry {
+	/// The date the work was logged on.
+	pub logged_date: NaiveDate,
+	/// How long the work session lasted.
+	pub duration: Duration,
+}
+
+/// The priority of a task, used to break ties when ordering otherwise-equivalent tasks.
+#[repr(C)]
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+	#[default]
+	Low,
+	Medium,
+	High,
+}
+
+impl std::str::FromStr for Priority {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"low" => Ok(Priority::Low),
+			"medium" => Ok(Priority::Medium),
+			"high" => Ok(Priority::High),
+			other => Err(format!("unknown priority: {}", other)),
+		}
+	}
+}
+
+/// Orders an optional due date so that tasks with no due date sort last.
+fn due_order(due: &Option<DateTime<Local>>) -> (u8, Option<DateTime<Local>>) {
+	match due {
+		Some(due) => (0, Some(*due)),
+		None => (1, None),
+	}
+}
+
+/// Orders two named tasks by due date (no due date last), breaking ties so that among
+/// otherwise-equivalent tasks the higher priority surfaces first.
+fn task_order(name_a: &str, a: &Task, name_b: &str, b: &Task) -> std::cmp::Ordering {
+	due_order(&a.due)
+		.cmp(&due_order(&b.due))
+		.then_with(|| b.priority.cmp(&a.priority))
+		.then_with(|| name_a.cmp(name_b))
 }
@@ -0,0 +1,60 @@
+use tasktree_core::{Priority, Task, Tree};
+
+fn task(depends_on: &[&str]) -> Task {
+	let mut task = Task::default();
+	task.depends_on = depends_on.iter().map(|s| s.to_string()).collect();
+	task
+}
+
+fn tree_of(tasks: Vec<(&str, Task)>) -> Tree {
+	let mut tree = Tree::default();
+	for (name, task) in tasks {
+		tree.tasks.insert(name.to_string(), task);
+	}
+	tree.populate_tree().unwrap();
+	tree
+}
+
+#[test]
+fn ready_order_respects_dependencies() {
+	let tree = tree_of(vec![
+		("a", task(&[])),
+		("b", task(&["a"])),
+		("c", task(&["b"])),
+	]);
+	assert_eq!(tree.ready_order().unwrap(), vec!["a", "b", "c"]);
+}
+
+#[test]
+fn ready_order_breaks_ties_by_priority_then_name() {
+	let mut high = task(&[]);
+	high.priority = Priority::High;
+	let tree = tree_of(vec![("a", task(&[])), ("c", high.clone()), ("b", high)]);
+	assert_eq!(tree.ready_order().unwrap(), vec!["b", "c", "a"]);
+}
+
+#[test]
+fn ready_order_excludes_symbolic_tasks_but_unblocks_their_dependents() {
+	let mut milestone = task(&["base"]);
+	milestone.symbolic = true;
+	let tree = tree_of(vec![
+		("base", task(&[])),
+		("milestone", milestone),
+		("after", task(&["milestone"])),
+	]);
+	assert_eq!(tree.ready_order().unwrap(), vec!["base", "after"]);
+}
+
+#[test]
+fn ready_order_omits_completed_tasks() {
+	let mut done = task(&[]);
+	done.complete = true;
+	let tree = tree_of(vec![("done", done), ("next", task(&["done"]))]);
+	assert_eq!(tree.ready_order().unwrap(), vec!["next"]);
+}
+
+#[test]
+fn ready_order_refuses_cyclic_dependencies() {
+	let tree = tree_of(vec![("a", task(&["b"])), ("b", task(&["a"]))]);
+	assert!(tree.ready_order().is_err());
+}